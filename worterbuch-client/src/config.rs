@@ -0,0 +1,64 @@
+use crate::ws::TlsConfig;
+use std::{env, str::FromStr, time::Duration};
+use worterbuch_common::error::ConnectionResult;
+
+/// Runtime configuration for a websocket [`Connection`](crate::Connection),
+/// assembled from environment variables via [`Config::new_ws`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub proto: String,
+    pub host_addr: String,
+    pub port: u16,
+    /// Interval between keepalive pings sent to the server.
+    pub ping_interval: Duration,
+    /// How long the server may go without answering pings before the
+    /// connection counts as lost. A ping counts as missed when no pong has
+    /// arrived by the next `ping_interval` tick; the connection is dropped
+    /// after `ping_timeout / ping_interval` (at least one) missed pings in a row.
+    pub ping_timeout: Duration,
+    /// TLS options for `wss://` connections; `None` uses the default
+    /// `rustls` configuration with system root certificates.
+    pub tls: Option<TlsConfig>,
+    /// Capacity of the broadcast channel used to fan out server messages to
+    /// subscribers. Size this generously enough that a briefly slow
+    /// consumer doesn't miss messages — see
+    /// `broadcast::error::RecvError::Lagged`.
+    pub channel_capacity: usize,
+    /// Largest message (and frame) accepted from the server, in bytes.
+    /// `None` uses `tungstenite`'s default limit. A peer that exceeds this
+    /// is disconnected rather than letting an unbounded allocation through.
+    pub max_message_size: Option<usize>,
+}
+
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+impl Config {
+    /// Builds a [`Config`] for a websocket connection from environment
+    /// variables, falling back to sensible defaults when unset.
+    pub fn new_ws() -> ConnectionResult<Self> {
+        Ok(Config {
+            proto: env::var("WORTERBUCH_PROTO").unwrap_or_else(|_| "ws".to_owned()),
+            host_addr: env::var("WORTERBUCH_HOST_ADDRESS")
+                .unwrap_or_else(|_| "127.0.0.1".to_owned()),
+            port: env_or("WORTERBUCH_PORT", 8080),
+            // A 0s interval would make `tokio::time::interval` panic the
+            // moment the keepalive ticker fires, so clamp to a sane floor
+            // instead of trusting the env var verbatim.
+            ping_interval: Duration::from_secs(env_or("WORTERBUCH_PING_INTERVAL_SECS", 30).max(1)),
+            ping_timeout: Duration::from_secs(env_or("WORTERBUCH_PING_TIMEOUT_SECS", 10).max(1)),
+            // TLS material (custom roots, client certs) isn't the kind of
+            // thing we guess at from env vars; callers who need it set
+            // `config.tls` after construction.
+            tls: None,
+            channel_capacity: env_or("WORTERBUCH_CHANNEL_CAPACITY", 1_000),
+            max_message_size: env::var("WORTERBUCH_MAX_MESSAGE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+        })
+    }
+}
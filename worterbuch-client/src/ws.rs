@@ -3,12 +3,26 @@ use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
-use std::{future::Future, io};
-use tokio::{net::TcpStream, spawn, sync::broadcast, sync::mpsc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    net::TcpStream,
+    spawn,
+    sync::{broadcast, mpsc, Mutex, Notify},
+    time::{interval, sleep},
+};
 use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{self, Message},
-    MaybeTlsStream, WebSocketStream,
+    connect_async_tls_with_config,
+    tungstenite::{self, protocol::WebSocketConfig, Message},
+    Connector, MaybeTlsStream, WebSocketStream,
 };
 use worterbuch_common::{
     encode_message,
@@ -17,43 +31,575 @@ use worterbuch_common::{
     ClientMessage as CM, Handshake, ServerMessage as SM,
 };
 
+/// Connectivity lifecycle of a [`Connection`], broadcast to interested consumers
+/// so e.g. a UI can show a "reconnecting" indicator instead of silently stalling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Reconnecting,
+    /// The peer violated the protocol in a way that isn't just a dropped
+    /// socket, e.g. sending a message larger than `Config::max_message_size`.
+    /// Carries a human-readable description for logging/diagnostics.
+    PeerError(String),
+}
+
+/// Backoff parameters used by [`connect_resilient`] when the connection drops.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectConfig {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    /// Caps the number of tries for the *initial* connection only; once a
+    /// connection has been established, later drops are always retried
+    /// forever, since that's the whole point of `connect_resilient`. `None`
+    /// (the default) retries the initial attempt forever too; set this when
+    /// a caller would rather fail fast on a bad host/port than hang
+    /// indefinitely.
+    pub max_initial_attempts: Option<u32>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            max_initial_attempts: None,
+        }
+    }
+}
+
+/// TLS options for connecting to a `wss://` endpoint that isn't trusted by
+/// the system root store out of the box, e.g. a private CA or mTLS setup.
+/// When absent, `connect_async`'s default `rustls` configuration (system
+/// roots, no client certificate) is used.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM-encoded custom root CA bundle. If `None`, the default webpki
+    /// roots are used.
+    pub root_cert_pem: Option<Vec<u8>>,
+    /// PEM-encoded (certificate, private key) pair for mutual TLS.
+    pub client_identity_pem: Option<(Vec<u8>, Vec<u8>)>,
+    /// Disables certificate verification entirely. For local development
+    /// against self-signed servers only - never enable this in production.
+    pub danger_accept_invalid_certs: bool,
+}
+
+mod danger {
+    use rustls::client::{ServerCertVerified, ServerCertVerifier};
+
+    /// Accepts any certificate presented by the server. Only ever wired up
+    /// when [`super::TlsConfig::danger_accept_invalid_certs`] is set.
+    pub struct NoCertificateVerification;
+
+    impl ServerCertVerifier for NoCertificateVerification {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &rustls::Certificate,
+            _intermediates: &[rustls::Certificate],
+            _server_name: &rustls::ServerName,
+            _scts: &mut dyn Iterator<Item = &[u8]>,
+            _ocsp_response: &[u8],
+            _now: std::time::SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+    }
+}
+
+fn build_connector(tls: &TlsConfig) -> ConnectionResult<Connector> {
+    fn io_err(e: impl std::fmt::Display) -> ConnectionError {
+        ConnectionError::IoError(io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    let mut roots = rustls::RootCertStore::empty();
+    if let Some(pem) = &tls.root_cert_pem {
+        for cert in rustls_pemfile::certs(&mut &pem[..]).map_err(io_err)? {
+            roots.add(&rustls::Certificate(cert)).map_err(io_err)?;
+        }
+    } else {
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+            rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                ta.subject,
+                ta.spki,
+                ta.name_constraints,
+            )
+        }));
+    }
+
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+
+    let mut config = if let Some((cert_pem, key_pem)) = &tls.client_identity_pem {
+        let certs = rustls_pemfile::certs(&mut &cert_pem[..])
+            .map_err(io_err)?
+            .into_iter()
+            .map(rustls::Certificate)
+            .collect();
+        let key = rustls_pemfile::pkcs8_private_keys(&mut &key_pem[..])
+            .map_err(io_err)?
+            .into_iter()
+            .next()
+            .map(rustls::PrivateKey)
+            .ok_or_else(|| io_err("client_identity_pem contains no private key"))?;
+        builder.with_client_auth_cert(certs, key).map_err(io_err)?
+    } else {
+        builder.with_no_client_auth()
+    };
+
+    if tls.danger_accept_invalid_certs {
+        log::warn!("TLS certificate verification is disabled - do not use in production");
+        config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(danger::NoCertificateVerification));
+    }
+
+    Ok(Connector::Rustls(Arc::new(config)))
+}
+
+/// Builds the `tungstenite` socket config for `max_message_size`, reusing the
+/// same limit for both the message and its frames since messages this client
+/// never splits across multiple frames itself.
+fn build_ws_config(max_message_size: Option<usize>) -> Option<WebSocketConfig> {
+    max_message_size.map(|max| WebSocketConfig {
+        max_message_size: Some(max),
+        max_frame_size: Some(max),
+        ..Default::default()
+    })
+}
+
+/// If `e` is the peer exceeding `Config::max_message_size`, returns the
+/// [`ConnectionError`] to report for it; `None` for any other socket error,
+/// which callers keep treating as a plain disconnect.
+fn oversized_message_error(e: &tungstenite::Error) -> Option<ConnectionError> {
+    match e {
+        tungstenite::Error::Capacity(cap_err) => Some(ConnectionError::IoError(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("server message exceeded the configured size limit: {cap_err}"),
+        ))),
+        _ => None,
+    }
+}
+
+/// If `msg` establishes long-lived server-side state (a subscription),
+/// returns the transaction id it should be replayed under against a freshly
+/// (re-)established socket.
+fn long_lived_transaction_id(msg: &CM) -> Option<u64> {
+    match msg {
+        CM::Subscribe(m) => Some(m.transaction_id),
+        CM::PSubscribe(m) => Some(m.transaction_id),
+        CM::SubscribeLs(m) => Some(m.transaction_id),
+        _ => None,
+    }
+}
+
+/// If `msg` cancels a previously tracked subscription, returns the
+/// transaction id it cancels so the replay set can drop it.
+fn cancelled_transaction_id(msg: &CM) -> Option<u64> {
+    match msg {
+        CM::Unsubscribe(m) => Some(m.transaction_id),
+        CM::UnsubscribeLs(m) => Some(m.transaction_id),
+        _ => None,
+    }
+}
+
+fn backoff_delay(attempt: u32, config: &ReconnectConfig) -> Duration {
+    let exp = config.base_delay.saturating_mul(1 << attempt.min(16));
+    let capped = exp.min(config.max_delay);
+    if !config.jitter {
+        return capped;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let jitter_millis = (nanos % 100) as u64;
+    capped + Duration::from_millis(jitter_millis)
+}
+
 pub async fn connect_with_default_config<F: Future<Output = ()> + Send + 'static>(
     on_disconnect: F,
 ) -> ConnectionResult<(Connection, Config)> {
     let config = Config::new_ws()?;
     Ok((
-        connect(&config.proto, &config.host_addr, config.port, on_disconnect).await?,
+        connect(
+            &config.proto,
+            &config.host_addr,
+            config.port,
+            config.ping_interval,
+            config.ping_timeout,
+            config.tls.as_ref(),
+            config.channel_capacity,
+            config.max_message_size,
+            on_disconnect,
+        )
+        .await?,
         config,
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn connect<F: Future<Output = ()> + Send + 'static>(
     proto: &str,
     host_addr: &str,
     port: u16,
+    ping_interval: Duration,
+    ping_timeout: Duration,
+    tls: Option<&TlsConfig>,
+    channel_capacity: usize,
+    max_message_size: Option<usize>,
     on_disconnect: F,
 ) -> ConnectionResult<Connection> {
-    let url = format!("{proto}://{host_addr}:{port}/ws");
-    let (server, _) = connect_async(url).await?;
-    let (ws_tx, mut ws_rx) = server.split();
+    let (handshake, ws_tx, ws_rx) =
+        try_connect_once(proto, host_addr, port, tls, max_message_size).await?;
 
     let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
-    let (result_tx, result_rx) = broadcast::channel(1_000);
+    let (result_tx, result_rx) = broadcast::channel(channel_capacity);
+
+    connected(
+        ws_tx,
+        ws_rx,
+        cmd_tx,
+        cmd_rx,
+        result_tx,
+        result_rx,
+        on_disconnect,
+        handshake,
+        ping_interval,
+        ping_timeout,
+        channel_capacity,
+    )
+}
+
+/// Like [`connect_with_default_config`], but supervises the connection for its
+/// entire lifetime: on disconnect it transparently reconnects using exponential
+/// backoff and replays every subscription the client had established, instead
+/// of leaving the returned [`Connection`] permanently dead.
+pub async fn connect_resilient(
+    reconnect_config: ReconnectConfig,
+) -> ConnectionResult<(Connection, Config, broadcast::Receiver<ConnectionState>)> {
+    let config = Config::new_ws()?;
+    let (state_tx, state_rx) = broadcast::channel(16);
+
+    let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel::<CM>();
+    let (result_tx, result_rx) = broadcast::channel(config.channel_capacity);
+    let channel_capacity = config.channel_capacity;
+    let replay: Arc<Mutex<HashMap<u64, CM>>> = Arc::new(Mutex::new(HashMap::new()));
+
+    let tls = config.tls.clone().map(Arc::new);
+    let max_message_size = config.max_message_size;
+
+    let _ = state_tx.send(ConnectionState::Connecting);
+    let (handshake, mut ws_tx, mut ws_rx) = connect_until_established(
+        &config.proto,
+        &config.host_addr,
+        config.port,
+        tls.as_deref(),
+        max_message_size,
+        &reconnect_config,
+        reconnect_config.max_initial_attempts,
+        &state_tx,
+    )
+    .await?;
+    let _ = state_tx.send(ConnectionState::Connected);
+
+    let separator = handshake.separator;
+    let wildcard = handshake.wildcard;
+    let multi_wildcard = handshake.multi_wildcard;
+
+    let proto = config.proto.clone();
+    let host_addr = config.host_addr.clone();
+    let port = config.port;
+    let ping_interval = config.ping_interval;
+    let max_missed_pongs = max_missed_pongs(ping_interval, config.ping_timeout);
+
+    // A single task owns both halves of the socket for its entire lifetime so
+    // that reconnecting (which replaces both halves at once) never races with
+    // a concurrently running sender. Commands, keepalive pings and inbound
+    // frames are all driven from the one select loop below.
+    spawn(async move {
+        let mut ticker = interval(ping_interval);
+        let mut missed_pongs = 0u32;
+        let mut pong_pending = false;
+        let mut dropped_total = 0u64;
+
+        loop {
+            tokio::select! {
+                cmd = cmd_rx.recv() => {
+                    match cmd {
+                        Some(msg) => {
+                            if let Some(tid) = long_lived_transaction_id(&msg) {
+                                replay.lock().await.insert(tid, msg.clone());
+                            } else if let Some(tid) = cancelled_transaction_id(&msg) {
+                                replay.lock().await.remove(&tid);
+                            }
+                            if let Ok(data) = encode_message(&msg) {
+                                if let Err(e) = ws_tx.send(tungstenite::Message::Binary(data)).await {
+                                    log::error!("failed to send tcp message: {e}");
+                                }
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                _ = ticker.tick() => {
+                    if pong_pending {
+                        missed_pongs += 1;
+                    } else {
+                        missed_pongs = 0;
+                    }
+                    if missed_pongs >= max_missed_pongs {
+                        log::error!("peer did not respond to {missed_pongs} consecutive pings");
+                        if !reconnect(
+                            &proto, &host_addr, port, tls.as_deref(), max_message_size, &reconnect_config, &state_tx, &replay,
+                            &mut ws_tx, &mut ws_rx, &mut ticker, ping_interval,
+                        )
+                        .await
+                        {
+                            break;
+                        }
+                        missed_pongs = 0;
+                        pong_pending = false;
+                        continue;
+                    }
+                    pong_pending = true;
+                    if ws_tx.send(Message::Ping(Vec::new())).await.is_err() {
+                        if !reconnect(
+                            &proto, &host_addr, port, tls.as_deref(), max_message_size, &reconnect_config, &state_tx, &replay,
+                            &mut ws_tx, &mut ws_rx, &mut ticker, ping_interval,
+                        )
+                        .await
+                        {
+                            break;
+                        }
+                        missed_pongs = 0;
+                        pong_pending = false;
+                    }
+                }
+                incoming = ws_rx.next() => {
+                    let lost = match incoming {
+                        Some(Ok(msg)) if msg.is_ping() => {
+                            let payload = msg.into_data();
+                            let _ = ws_tx.send(Message::Pong(payload)).await;
+                            false
+                        }
+                        Some(Ok(msg)) if msg.is_pong() => {
+                            pong_pending = false;
+                            false
+                        }
+                        Some(Ok(msg)) if msg.is_close() => {
+                            log::info!("server closed the connection");
+                            true
+                        }
+                        Some(Ok(msg)) if msg.is_binary() || msg.is_text() => {
+                            let data = msg.into_data();
+                            match read_server_message(&*data).await {
+                                Ok(Some(sm)) => {
+                                    forward_server_message(
+                                        &result_tx, channel_capacity, &mut dropped_total, sm,
+                                    );
+                                    false
+                                }
+                                Ok(None) => true,
+                                Err(e) => {
+                                    log::error!("Error decoding message: {e}");
+                                    false
+                                }
+                            }
+                        }
+                        Some(Ok(_)) => false,
+                        Some(Err(e)) => {
+                            if let Some(err) = oversized_message_error(&e) {
+                                log::error!("disconnecting peer: {err}");
+                                let _ = state_tx.send(ConnectionState::PeerError(err.to_string()));
+                            } else {
+                                log::error!("Error receiving server message: {e}");
+                            }
+                            true
+                        }
+                        None => true,
+                    };
+                    if lost {
+                        if !reconnect(
+                            &proto, &host_addr, port, tls.as_deref(), max_message_size, &reconnect_config, &state_tx, &replay,
+                            &mut ws_tx, &mut ws_rx, &mut ticker, ping_interval,
+                        )
+                        .await
+                        {
+                            break;
+                        }
+                        missed_pongs = 0;
+                        pong_pending = false;
+                    }
+                }
+            }
+        }
+    });
+
+    drop(result_rx);
 
+    Ok((
+        Connection::new(cmd_tx, result_tx, separator, wildcard, multi_wildcard),
+        config,
+        state_rx,
+    ))
+}
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type WsSource = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+async fn open_socket(
+    proto: &str,
+    host_addr: &str,
+    port: u16,
+    tls: Option<&TlsConfig>,
+    max_message_size: Option<usize>,
+) -> ConnectionResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let url = format!("{proto}://{host_addr}:{port}/ws");
+    let connector = tls.map(build_connector).transpose()?;
+    let ws_config = build_ws_config(max_message_size);
+    let (server, _) = connect_async_tls_with_config(url, ws_config, false, connector).await?;
+    Ok(server)
+}
+
+/// Connects and waits for the server's handshake, retrying with exponential
+/// backoff (plus jitter). Retries forever when `max_attempts` is `None`;
+/// otherwise gives up and returns the last error once `max_attempts` tries
+/// have failed.
+#[allow(clippy::too_many_arguments)]
+async fn connect_until_established(
+    proto: &str,
+    host_addr: &str,
+    port: u16,
+    tls: Option<&TlsConfig>,
+    max_message_size: Option<usize>,
+    reconnect_config: &ReconnectConfig,
+    max_attempts: Option<u32>,
+    state_tx: &broadcast::Sender<ConnectionState>,
+) -> ConnectionResult<(Handshake, WsSink, WsSource)> {
+    let mut attempt = 0;
+    loop {
+        match try_connect_once(proto, host_addr, port, tls, max_message_size).await {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                if max_attempts.is_some_and(|max| attempt.saturating_add(1) >= max) {
+                    let failed = attempt.saturating_add(1);
+                    log::error!("giving up after {failed} failed connection attempt(s): {e}");
+                    return Err(e);
+                }
+                log::warn!("connection attempt failed: {e}");
+                let _ = state_tx.send(ConnectionState::Reconnecting);
+                sleep(backoff_delay(attempt, reconnect_config)).await;
+                attempt = attempt.saturating_add(1);
+            }
+        }
+    }
+}
+
+/// How many ping intervals a peer may miss in a row before it is considered
+/// unreachable, derived from the ratio of `ping_timeout` to `ping_interval`.
+fn max_missed_pongs(ping_interval: Duration, ping_timeout: Duration) -> u32 {
+    let ratio = ping_timeout.as_millis() / ping_interval.as_millis().max(1);
+    ratio.max(1) as u32
+}
+
+/// Forwards `msg` to subscribers, counting (and logging) a running total of
+/// messages dropped from the broadcast buffer. `broadcast::Receiver::recv`
+/// only tells a receiver it lagged once it's too late to do anything about
+/// the drop, and only tells the receiver that happened to be slow - tracking
+/// it here instead, on the sender side, counts every drop exactly once
+/// regardless of which subscriber (if any) was behind. The check is an
+/// approximation: once `channel_capacity` messages are queued and unread,
+/// the next send evicts the oldest one for whoever hasn't read it yet.
+fn forward_server_message(
+    result_tx: &broadcast::Sender<SM>,
+    channel_capacity: usize,
+    dropped_total: &mut u64,
+    msg: SM,
+) {
+    if result_tx.len() >= channel_capacity {
+        *dropped_total += 1;
+        log::warn!(
+            "broadcast buffer full ({channel_capacity} messages queued); oldest unread \
+             server message dropped ({dropped_total} dropped total since connect) - a \
+             lagging subscriber will see RecvError::Lagged on its next recv"
+        );
+    }
+    if let Err(e) = result_tx.send(msg) {
+        log::error!("Error forwarding server message: {e}");
+    }
+}
+
+/// Re-establishes the socket in place and replays every tracked subscription
+/// against it. Returns `false` if the supervisor should give up for good.
+#[allow(clippy::too_many_arguments)]
+async fn reconnect(
+    proto: &str,
+    host_addr: &str,
+    port: u16,
+    tls: Option<&TlsConfig>,
+    max_message_size: Option<usize>,
+    reconnect_config: &ReconnectConfig,
+    state_tx: &broadcast::Sender<ConnectionState>,
+    replay: &Arc<Mutex<HashMap<u64, CM>>>,
+    ws_tx: &mut WsSink,
+    ws_rx: &mut WsSource,
+    ticker: &mut tokio::time::Interval,
+    ping_interval: Duration,
+) -> bool {
+    let _ = state_tx.send(ConnectionState::Reconnecting);
+    // Once a connection has been established, later drops always retry
+    // forever regardless of `max_initial_attempts` — that bound only
+    // protects the initial connect from hanging against a bad host/port.
+    match connect_until_established(
+        proto,
+        host_addr,
+        port,
+        tls,
+        max_message_size,
+        reconnect_config,
+        None,
+        state_tx,
+    )
+    .await
+    {
+        Ok((_handshake, new_ws_tx, new_ws_rx)) => {
+            *ws_tx = new_ws_tx;
+            *ws_rx = new_ws_rx;
+            *ticker = interval(ping_interval);
+            for msg in replay.lock().await.values() {
+                if let Ok(data) = encode_message(msg) {
+                    if let Err(e) = ws_tx.send(tungstenite::Message::Binary(data)).await {
+                        log::error!("failed to replay subscription: {e}");
+                    }
+                }
+            }
+            let _ = state_tx.send(ConnectionState::Connected);
+            true
+        }
+        Err(e) => {
+            log::error!("giving up reconnecting: {e}");
+            false
+        }
+    }
+}
+
+async fn try_connect_once(
+    proto: &str,
+    host_addr: &str,
+    port: u16,
+    tls: Option<&TlsConfig>,
+    max_message_size: Option<usize>,
+) -> ConnectionResult<(Handshake, WsSink, WsSource)> {
+    let server = open_socket(proto, host_addr, port, tls, max_message_size).await?;
+    let (ws_tx, mut ws_rx) = server.split();
     match ws_rx.next().await {
         Some(Ok(msg)) => {
             let data = msg.into_data();
             match read_server_message(&*data).await? {
-                Some(SM::Handshake(handshake)) => connected(
-                    ws_tx,
-                    ws_rx,
-                    cmd_tx,
-                    cmd_rx,
-                    result_tx,
-                    result_rx,
-                    on_disconnect,
-                    handshake,
-                ),
+                Some(SM::Handshake(handshake)) => Ok((handshake, ws_tx, ws_rx)),
                 Some(other) => Err(ConnectionError::IoError(io::Error::new(
                     io::ErrorKind::InvalidData,
                     format!("server sendt invalid handshake message: {other:?}"),
@@ -72,6 +618,7 @@ pub async fn connect<F: Future<Output = ()> + Send + 'static>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn connected<F: Future<Output = ()> + Send + 'static>(
     mut ws_tx: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
     mut ws_rx: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
@@ -81,43 +628,138 @@ fn connected<F: Future<Output = ()> + Send + 'static>(
     result_rx: broadcast::Receiver<SM>,
     on_disconnect: F,
     handshake: Handshake,
+    ping_interval: Duration,
+    ping_timeout: Duration,
+    channel_capacity: usize,
 ) -> Result<Connection, ConnectionError> {
     let result_tx_recv = result_tx.clone();
 
+    // Control frames (pings we send, pongs we answer with) are routed through
+    // this channel so the receive task, which spots inbound pings/pongs, can
+    // have the send task (the sole owner of `ws_tx`) write them out.
+    let (ctrl_tx, mut ctrl_rx) = mpsc::unbounded_channel::<Message>();
+    let pong_pending = Arc::new(AtomicBool::new(false));
+    let dead = Arc::new(Notify::new());
+
+    let keepalive_ctrl_tx = ctrl_tx.clone();
+    let keepalive_pong_pending = pong_pending.clone();
+    let keepalive_dead = dead.clone();
+    let max_missed_pongs = max_missed_pongs(ping_interval, ping_timeout);
     spawn(async move {
-        while let Some(msg) = cmd_rx.recv().await {
-            if let Ok(Some(data)) = encode_message(&msg).map(Some) {
-                let msg = tungstenite::Message::Binary(data);
-                if let Err(e) = ws_tx.send(msg).await {
-                    log::error!("failed to send tcp message: {e}");
-                    break;
-                }
+        let mut ticker = interval(ping_interval);
+        let mut missed = 0u32;
+        loop {
+            ticker.tick().await;
+            if keepalive_pong_pending.swap(true, Ordering::SeqCst) {
+                missed += 1;
             } else {
+                missed = 0;
+            }
+            if missed >= max_missed_pongs {
+                log::error!("peer did not respond to {missed} consecutive pings");
+                keepalive_dead.notify_waiters();
+                break;
+            }
+            if keepalive_ctrl_tx.send(Message::Ping(Vec::new())).is_err() {
                 break;
             }
         }
+    });
+
+    spawn(async move {
+        loop {
+            tokio::select! {
+                msg = cmd_rx.recv() => {
+                    match msg {
+                        Some(msg) => {
+                            if let Ok(data) = encode_message(&msg) {
+                                let msg = tungstenite::Message::Binary(data);
+                                if let Err(e) = ws_tx.send(msg).await {
+                                    log::error!("failed to send tcp message: {e}");
+                                    break;
+                                }
+                            } else {
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                ctrl = ctrl_rx.recv() => {
+                    match ctrl {
+                        Some(frame) => {
+                            if let Err(e) = ws_tx.send(frame).await {
+                                log::error!("failed to send control frame: {e}");
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
         // make sure initial rx is not dropped as long as stdin is read
         drop(result_rx);
     });
 
     spawn(async move {
+        let mut dropped_total = 0u64;
         loop {
-            if let Some(Ok(incoming_msg)) = ws_rx.next().await {
-                if incoming_msg.is_binary() {
-                    let data = incoming_msg.into_data();
-                    match read_server_message(&*data).await {
-                        Ok(Some(msg)) => {
-                            if let Err(e) = result_tx_recv.send(msg) {
-                                log::error!("Error forwarding server message: {e}");
+            tokio::select! {
+                _ = dead.notified() => {
+                    log::error!("Connection to server lost.");
+                    on_disconnect.await;
+                    break;
+                }
+                next = ws_rx.next() => {
+                    match next {
+                        Some(Ok(incoming_msg)) if incoming_msg.is_ping() => {
+                            let payload = incoming_msg.into_data();
+                            let _ = ctrl_tx.send(Message::Pong(payload));
+                        }
+                        Some(Ok(incoming_msg)) if incoming_msg.is_pong() => {
+                            pong_pending.store(false, Ordering::SeqCst);
+                        }
+                        Some(Ok(incoming_msg)) if incoming_msg.is_close() => {
+                            log::info!("server closed the connection");
+                            on_disconnect.await;
+                            break;
+                        }
+                        Some(Ok(incoming_msg)) if incoming_msg.is_binary() || incoming_msg.is_text() => {
+                            let data = incoming_msg.into_data();
+                            match read_server_message(&*data).await {
+                                Ok(Some(msg)) => {
+                                    forward_server_message(
+                                        &result_tx_recv, channel_capacity, &mut dropped_total, msg,
+                                    );
+                                }
+                                Ok(None) => {
+                                    log::error!("Connection to server lost.");
+                                    on_disconnect.await;
+                                    break;
+                                }
+                                Err(e) => {
+                                    log::error!("Error decoding message: {e}");
+                                }
                             }
                         }
-                        Ok(None) => {
-                            log::error!("Connection to server lost.");
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            // No state channel here (unlike connect_resilient), so a
+                            // peer that exceeds max_message_size just gets a more
+                            // specific log line before the connection is torn down.
+                            if let Some(err) = oversized_message_error(&e) {
+                                log::error!("disconnecting peer: {err}");
+                            } else {
+                                log::error!("Error receiving server message: {e}");
+                            }
                             on_disconnect.await;
                             break;
                         }
-                        Err(e) => {
-                            log::error!("Error decoding message: {e}");
+                        None => {
+                            log::error!("Connection to server lost.");
+                            on_disconnect.await;
+                            break;
                         }
                     }
                 }
@@ -137,3 +779,125 @@ fn connected<F: Future<Output = ()> + Send + 'static>(
         multi_wildcard,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use worterbuch_common::{PSubscribe, Subscribe, SubscribeLs, Unsubscribe, UnsubscribeLs};
+
+    #[test]
+    fn backoff_delay_doubles_until_capped_at_max_delay() {
+        let config = ReconnectConfig {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            jitter: false,
+            max_initial_attempts: None,
+        };
+        assert_eq!(backoff_delay(0, &config), Duration::from_millis(250));
+        assert_eq!(backoff_delay(1, &config), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, &config), Duration::from_millis(1_000));
+        assert_eq!(backoff_delay(30, &config), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn backoff_delay_does_not_overflow_on_large_attempt_counts() {
+        let config = ReconnectConfig {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            jitter: false,
+            max_initial_attempts: None,
+        };
+        assert_eq!(backoff_delay(u32::MAX, &config), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn backoff_delay_jitter_stays_within_expected_bounds() {
+        let config = ReconnectConfig {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            max_initial_attempts: None,
+        };
+        let delay = backoff_delay(0, &config);
+        assert!(delay >= Duration::from_millis(250));
+        assert!(delay < Duration::from_millis(350));
+    }
+
+    #[test]
+    fn long_lived_transaction_id_matches_subscription_variants() {
+        let sub = CM::Subscribe(Subscribe {
+            transaction_id: 1,
+            key: "a/b".to_owned(),
+            unique: false,
+        });
+        let psub = CM::PSubscribe(PSubscribe {
+            transaction_id: 2,
+            request_pattern: "a/#".to_owned(),
+            unique: false,
+        });
+        let subls = CM::SubscribeLs(SubscribeLs {
+            transaction_id: 3,
+            parent: None,
+        });
+        assert_eq!(long_lived_transaction_id(&sub), Some(1));
+        assert_eq!(long_lived_transaction_id(&psub), Some(2));
+        assert_eq!(long_lived_transaction_id(&subls), Some(3));
+    }
+
+    #[test]
+    fn long_lived_transaction_id_ignores_non_subscription_messages() {
+        let unsub = CM::Unsubscribe(Unsubscribe { transaction_id: 1 });
+        assert_eq!(long_lived_transaction_id(&unsub), None);
+    }
+
+    #[test]
+    fn cancelled_transaction_id_matches_unsubscribe_variants() {
+        let unsub = CM::Unsubscribe(Unsubscribe { transaction_id: 5 });
+        let unsubls = CM::UnsubscribeLs(UnsubscribeLs { transaction_id: 6 });
+        assert_eq!(cancelled_transaction_id(&unsub), Some(5));
+        assert_eq!(cancelled_transaction_id(&unsubls), Some(6));
+    }
+
+    #[test]
+    fn cancelled_transaction_id_ignores_subscribe_messages() {
+        // A Subscribe establishes long-lived state rather than cancelling it,
+        // so it must never remove an entry from the replay set.
+        let sub = CM::Subscribe(Subscribe {
+            transaction_id: 1,
+            key: "a/b".to_owned(),
+            unique: false,
+        });
+        assert_eq!(cancelled_transaction_id(&sub), None);
+    }
+
+    #[test]
+    fn max_missed_pongs_is_the_timeout_to_interval_ratio() {
+        assert_eq!(
+            max_missed_pongs(Duration::from_secs(10), Duration::from_secs(30)),
+            3
+        );
+    }
+
+    #[test]
+    fn max_missed_pongs_is_at_least_one_even_with_a_longer_interval_than_timeout() {
+        assert_eq!(
+            max_missed_pongs(Duration::from_secs(30), Duration::from_secs(10)),
+            1
+        );
+    }
+
+    #[test]
+    fn oversized_message_error_detects_capacity_errors() {
+        let e = tungstenite::Error::Capacity(tungstenite::error::CapacityError::MessageTooLong {
+            size: 2_000_000,
+            max_size: 1_000_000,
+        });
+        assert!(oversized_message_error(&e).is_some());
+    }
+
+    #[test]
+    fn oversized_message_error_ignores_other_errors() {
+        let e = tungstenite::Error::ConnectionClosed;
+        assert!(oversized_message_error(&e).is_none());
+    }
+}